@@ -1,13 +1,64 @@
+use std::convert::Infallible;
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::{extract::State, Json};
-use serde::Serialize;
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
+};
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, watch, RwLock};
+use tokio_stream::{wrappers::BroadcastStream, StreamExt};
 
 use crate::error::AppError;
+use crate::insights::{FeeInsightsEngine, SurgeClassification};
 use crate::services::horizon::HorizonClient;
+use crate::store::{FeeHistoryStore, FeeSnapshot};
 
-/// Shared state type for the fees route.
-pub type FeesState = Arc<HorizonClient>;
+/// Shared state for `/fees/current`: a live Horizon client plus the
+/// insights engine's rolling utilization/surge signal.
+#[derive(Clone)]
+pub struct FeesState {
+    pub horizon: Arc<HorizonClient>,
+    pub insights: Arc<RwLock<FeeInsightsEngine>>,
+}
+
+/// Shared state type for the fee-history route.
+pub type FeeHistoryState = Arc<RwLock<FeeHistoryStore>>;
+
+/// Shared state for the fee-stream (SSE) route: clients subscribe to the
+/// same broadcast channel the poller publishes each snapshot on, and the
+/// stream is cut short on `shutdown` so a connected client never holds the
+/// graceful shutdown open indefinitely (an `Sse` body otherwise only ends
+/// when the client disconnects or the sender is dropped).
+#[derive(Clone)]
+pub struct FeeStreamState {
+    pub updates: broadcast::Sender<FeeSnapshot>,
+    pub shutdown: watch::Receiver<bool>,
+}
+
+/// Shared state for `/fees/recommend`: the recent fee history plus the
+/// insights engine's surge signal, used to nudge the suggestion upward.
+#[derive(Clone)]
+pub struct FeeRecommendState {
+    pub history: Arc<RwLock<FeeHistoryStore>>,
+    pub insights: Arc<RwLock<FeeInsightsEngine>>,
+}
+
+/// Default number of entries returned by `/fees/history` when `count` is omitted.
+const DEFAULT_HISTORY_COUNT: usize = 100;
+
+/// How often a keep-alive comment is sent on an idle `/fees/stream` connection.
+const SSE_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How many recent snapshots `/fees/recommend` draws its percentile from.
+const DEFAULT_RECOMMEND_WINDOW: usize = 20;
+
+/// Multiplier applied to the recommended fee when the insights engine
+/// classifies recent ledger utilization as `SurgeLikely`.
+const SURGE_NUDGE_FACTOR: f64 = 1.2;
 
 #[derive(Serialize)]
 pub struct PercentileFees {
@@ -26,18 +77,27 @@ pub struct CurrentFeeResponse {
     pub max_fee: String,
     pub avg_fee: String,
     pub percentiles: PercentileFees,
+    /// Most recently observed ledger-utilization ratio (0.0-1.0), if the
+    /// poller has recorded one yet.
+    pub utilization_ratio: Option<f64>,
+    pub surge: SurgeClassification,
 }
 
 pub async fn current_fees(
-    State(client): State<FeesState>,
+    State(state): State<FeesState>,
 ) -> Result<Json<CurrentFeeResponse>, AppError> {
-    let stats = client.fetch_fee_stats().await?;
+    let stats = state.horizon.fetch_fee_stats().await?;
+    let insights = state.insights.read().await;
+    let utilization_ratio = insights.latest_utilization();
+    let surge = insights.classification();
 
     Ok(Json(CurrentFeeResponse {
         base_fee: stats.last_ledger_base_fee,
         min_fee: stats.fee_charged.min,
         max_fee: stats.fee_charged.max,
         avg_fee: stats.fee_charged.avg,
+        utilization_ratio,
+        surge,
         percentiles: PercentileFees {
             p10: stats.fee_charged.p10,
             p25: stats.fee_charged.p25,
@@ -49,9 +109,245 @@ pub async fn current_fees(
     }))
 }
 
+#[derive(Deserialize)]
+pub struct FeeHistoryQuery {
+    pub count: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct FeeHistoryEntry {
+    pub base_fee: String,
+    pub min_fee: String,
+    pub max_fee: String,
+    pub avg_fee: String,
+    pub percentiles: PercentileFees,
+    pub polled_at: u64,
+}
+
+impl From<FeeSnapshot> for FeeHistoryEntry {
+    fn from(snapshot: FeeSnapshot) -> Self {
+        Self {
+            base_fee: snapshot.base_fee,
+            min_fee: snapshot.min_fee,
+            max_fee: snapshot.max_fee,
+            avg_fee: snapshot.avg_fee,
+            percentiles: PercentileFees {
+                p10: snapshot.p10,
+                p25: snapshot.p25,
+                p50: snapshot.p50,
+                p75: snapshot.p75,
+                p90: snapshot.p90,
+                p95: snapshot.p95,
+            },
+            polled_at: snapshot.polled_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct FeeHistoryResponse {
+    pub count: usize,
+    pub entries: Vec<FeeHistoryEntry>,
+}
+
+/// `GET /fees/history?count=N` — the last `N` polled fee snapshots, oldest
+/// first, so a caller can chart fee trends instead of only ever seeing the
+/// single current value exposed by `/fees/current`.
+pub async fn fee_history(
+    State(store): State<FeeHistoryState>,
+    Query(query): Query<FeeHistoryQuery>,
+) -> Result<Json<FeeHistoryResponse>, AppError> {
+    let count = query.count.unwrap_or(DEFAULT_HISTORY_COUNT);
+    let entries: Vec<FeeHistoryEntry> = store
+        .read()
+        .await
+        .last_n(count)
+        .into_iter()
+        .map(FeeHistoryEntry::from)
+        .collect();
+
+    Ok(Json(FeeHistoryResponse {
+        count: entries.len(),
+        entries,
+    }))
+}
+
+/// Which field of a snapshot a `/fees/stream` threshold filter applies to.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeMetric {
+    BaseFee,
+    P10,
+    P25,
+    P50,
+    P75,
+    P90,
+    P95,
+}
+
+impl FeeMetric {
+    fn value_of(self, snapshot: &FeeSnapshot) -> Option<u64> {
+        let raw = match self {
+            FeeMetric::BaseFee => &snapshot.base_fee,
+            FeeMetric::P10 => &snapshot.p10,
+            FeeMetric::P25 => &snapshot.p25,
+            FeeMetric::P50 => &snapshot.p50,
+            FeeMetric::P75 => &snapshot.p75,
+            FeeMetric::P90 => &snapshot.p90,
+            FeeMetric::P95 => &snapshot.p95,
+        };
+        raw.parse().ok()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FeeStreamQuery {
+    /// Which field the `threshold` filter applies to (defaults to `base_fee`).
+    pub metric: Option<FeeMetric>,
+    /// Only forward snapshots whose `metric` is at or above this value.
+    pub threshold: Option<u64>,
+}
+
+/// Whether a polled snapshot should be forwarded to an SSE subscriber,
+/// given its optional metric/threshold filter.
+fn passes_threshold(snapshot: &FeeSnapshot, metric: FeeMetric, threshold: Option<u64>) -> bool {
+    match threshold {
+        None => true,
+        Some(threshold) => metric.value_of(snapshot).is_some_and(|value| value >= threshold),
+    }
+}
+
+/// `GET /fees/stream` — Server-Sent Events push of each newly polled fee
+/// snapshot, so dashboards get live updates without re-hitting Horizon on
+/// every page load. Optionally filtered to only emit events once a chosen
+/// metric crosses `threshold`.
+pub async fn fee_stream(
+    State(state): State<FeeStreamState>,
+    Query(query): Query<FeeStreamQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let metric = query.metric.unwrap_or(FeeMetric::BaseFee);
+    let threshold = query.threshold;
+
+    let stream = BroadcastStream::new(state.updates.subscribe()).filter_map(move |message| {
+        let snapshot = match message {
+            Ok(snapshot) => snapshot,
+            // A slow subscriber that missed some messages; just skip ahead.
+            Err(_) => return None,
+        };
+
+        if !passes_threshold(&snapshot, metric, threshold) {
+            return None;
+        }
+
+        let entry = FeeHistoryEntry::from(snapshot);
+        let data = serde_json::to_string(&entry).unwrap_or_default();
+        Some(Ok(Event::default().event("fee_update").data(data)))
+    });
+
+    // Ends the stream once `shutdown` flips, so a connected client can't hold
+    // `axum::serve(...).with_graceful_shutdown(...)` open forever.
+    let mut shutdown = state.shutdown;
+    let stream = futures::StreamExt::take_until(stream, async move {
+        let _ = shutdown.changed().await;
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(SSE_KEEP_ALIVE_INTERVAL))
+}
+
+/// How urgently a caller wants their transaction confirmed, and therefore
+/// which percentile of recently observed fees to recommend.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Urgency {
+    Low,
+    Medium,
+    High,
+}
+
+impl Urgency {
+    /// The percentile (0-100) of the recent fee window this urgency maps
+    /// to, along with its label for the response.
+    fn percentile(self) -> (f64, &'static str) {
+        match self {
+            Urgency::Low => (25.0, "p25"),
+            Urgency::Medium => (50.0, "p50"),
+            Urgency::High => (90.0, "p90"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FeeRecommendQuery {
+    pub urgency: Urgency,
+}
+
+#[derive(Serialize)]
+pub struct FeeRecommendationResponse {
+    pub urgency: Urgency,
+    pub recommended_fee: u64,
+    /// The percentile of the sample window the suggestion was based on,
+    /// before any surge nudge was applied.
+    pub based_on_percentile: String,
+    /// How many recent snapshots the percentile was computed over.
+    pub sample_window: usize,
+    /// Whether the suggestion was bumped up because recent ledger
+    /// utilization was classified as `SurgeLikely`.
+    pub surge_adjusted: bool,
+}
+
+/// The value at `percentile` (0-100) in `values`, using nearest-rank
+/// interpolation. `values` is sorted in place. `None` if `values` is empty.
+fn percentile_of(values: &mut [u64], percentile: f64) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let rank = ((percentile / 100.0) * (values.len() - 1) as f64).round() as usize;
+    values.get(rank).copied()
+}
+
+/// `GET /fees/recommend?urgency={low|medium|high}` — a suggested fee drawn
+/// from the percentile of recently observed base fees matching the caller's
+/// urgency, nudged upward if recent ledger utilization looks like a surge.
+pub async fn recommend_fee(
+    State(state): State<FeeRecommendState>,
+    Query(query): Query<FeeRecommendQuery>,
+) -> Result<Json<FeeRecommendationResponse>, AppError> {
+    let snapshots = state
+        .history
+        .read()
+        .await
+        .last_n(DEFAULT_RECOMMEND_WINDOW);
+    let sample_window = snapshots.len();
+
+    let mut base_fees: Vec<u64> = snapshots
+        .iter()
+        .filter_map(|snapshot| snapshot.base_fee.parse::<u64>().ok())
+        .collect();
+
+    let (percentile, label) = query.urgency.percentile();
+    let mut recommended_fee = percentile_of(&mut base_fees, percentile).ok_or_else(|| {
+        AppError::NotReady("no fee history available yet to recommend a fee".into())
+    })?;
+
+    let surge_adjusted = state.insights.read().await.classification() == SurgeClassification::SurgeLikely;
+    if surge_adjusted {
+        recommended_fee = (recommended_fee as f64 * SURGE_NUDGE_FACTOR).ceil() as u64;
+    }
+
+    Ok(Json(FeeRecommendationResponse {
+        urgency: query.urgency,
+        recommended_fee,
+        based_on_percentile: label.to_string(),
+        sample_window,
+        surge_adjusted,
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::insights::InsightsConfig;
 
     #[test]
     fn current_fee_response_serialises_with_percentiles() {
@@ -68,6 +364,8 @@ mod tests {
                 p90: "500".into(),
                 p95: "800".into(),
             },
+            utilization_ratio: Some(0.42),
+            surge: SurgeClassification::Normal,
         };
 
         let json = serde_json::to_value(&response).unwrap();
@@ -75,6 +373,8 @@ mod tests {
         assert_eq!(json["percentiles"]["p10"], "100");
         assert_eq!(json["percentiles"]["p50"], "150");
         assert_eq!(json["percentiles"]["p95"], "800");
+        assert_eq!(json["utilization_ratio"], 0.42);
+        assert_eq!(json["surge"], "normal");
     }
 
     #[test]
@@ -93,4 +393,168 @@ mod tests {
             assert!(!json[field].as_str().unwrap().is_empty());
         }
     }
+
+    fn sample_snapshot(base_fee: &str, polled_at: u64) -> FeeSnapshot {
+        let stats: crate::services::horizon::HorizonFeeStats = serde_json::from_str(&format!(
+            r#"{{"last_ledger_base_fee":"{base_fee}","fee_charged":{{"min":"100","max":"5000","avg":"213","p10":"100","p25":"100","p50":"150","p75":"300","p90":"500","p95":"800"}}}}"#
+        ))
+        .unwrap();
+        let mut snapshot = FeeSnapshot::from_stats(&stats);
+        snapshot.polled_at = polled_at;
+        snapshot
+    }
+
+    #[test]
+    fn fee_history_entry_carries_percentiles_and_timestamp() {
+        let entry: FeeHistoryEntry = sample_snapshot("100", 1_700_000_000).into();
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["base_fee"], "100");
+        assert_eq!(json["percentiles"]["p95"], "800");
+        assert_eq!(json["polled_at"], 1_700_000_000);
+    }
+
+    #[tokio::test]
+    async fn fee_history_returns_requested_count_oldest_first() {
+        let store = Arc::new(RwLock::new(FeeHistoryStore::new(10)));
+        for (i, base_fee) in ["100", "200", "300"].iter().enumerate() {
+            store
+                .write()
+                .await
+                .push(sample_snapshot(base_fee, 1_700_000_000 + i as u64));
+        }
+
+        let response = fee_history(
+            State(store),
+            Query(FeeHistoryQuery { count: Some(2) }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert_eq!(response.count, 2);
+        assert_eq!(response.entries[0].base_fee, "200");
+        assert_eq!(response.entries[1].base_fee, "300");
+    }
+
+    #[test]
+    fn fee_metric_reads_the_requested_field() {
+        let snapshot = sample_snapshot("100", 0);
+        assert_eq!(FeeMetric::BaseFee.value_of(&snapshot), Some(100));
+        assert_eq!(FeeMetric::P95.value_of(&snapshot), Some(800));
+    }
+
+    #[test]
+    fn passes_threshold_allows_everything_when_unset() {
+        let snapshot = sample_snapshot("100", 0);
+        assert!(passes_threshold(&snapshot, FeeMetric::BaseFee, None));
+    }
+
+    #[test]
+    fn passes_threshold_filters_below_the_configured_value() {
+        let snapshot = sample_snapshot("100", 0);
+        assert!(!passes_threshold(&snapshot, FeeMetric::BaseFee, Some(500)));
+
+        let surged = sample_snapshot("900", 0);
+        assert!(passes_threshold(&surged, FeeMetric::BaseFee, Some(500)));
+    }
+
+    #[test]
+    fn passes_threshold_applies_to_the_chosen_percentile() {
+        let snapshot = sample_snapshot("100", 0);
+        assert!(passes_threshold(&snapshot, FeeMetric::P95, Some(500)));
+        assert!(!passes_threshold(&snapshot, FeeMetric::P10, Some(500)));
+    }
+
+    #[test]
+    fn percentile_of_picks_nearest_rank() {
+        let mut values = vec![400, 100, 300, 200, 500];
+        assert_eq!(percentile_of(&mut values, 0.0), Some(100));
+        assert_eq!(percentile_of(&mut values, 50.0), Some(300));
+        assert_eq!(percentile_of(&mut values, 100.0), Some(500));
+    }
+
+    #[test]
+    fn percentile_of_is_none_for_an_empty_window() {
+        let mut values: Vec<u64> = vec![];
+        assert_eq!(percentile_of(&mut values, 50.0), None);
+    }
+
+    async fn recommend_state(insights_config: InsightsConfig) -> FeeRecommendState {
+        let store = Arc::new(RwLock::new(FeeHistoryStore::new(10)));
+        for (i, base_fee) in ["100", "200", "300", "400", "500"].iter().enumerate() {
+            store
+                .write()
+                .await
+                .push(sample_snapshot(base_fee, 1_700_000_000 + i as u64));
+        }
+
+        FeeRecommendState {
+            history: store,
+            insights: Arc::new(RwLock::new(FeeInsightsEngine::new(insights_config))),
+        }
+    }
+
+    #[tokio::test]
+    async fn recommend_fee_maps_urgency_to_the_expected_percentile() {
+        let state = recommend_state(InsightsConfig::default()).await;
+
+        let low = recommend_fee(
+            State(state.clone()),
+            Query(FeeRecommendQuery { urgency: Urgency::Low }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(low.based_on_percentile, "p25");
+        assert_eq!(low.sample_window, 5);
+        assert!(!low.surge_adjusted);
+
+        let high = recommend_fee(
+            State(state),
+            Query(FeeRecommendQuery { urgency: Urgency::High }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(high.based_on_percentile, "p90");
+        assert!(high.recommended_fee > low.recommended_fee);
+    }
+
+    #[tokio::test]
+    async fn recommend_fee_is_nudged_up_when_surge_is_likely() {
+        let state = recommend_state(InsightsConfig {
+            surge_ratio_threshold: 0.5,
+            surge_window: 4,
+        })
+        .await;
+        state.insights.write().await.record_utilization(0.9);
+
+        let recommendation = recommend_fee(
+            State(state),
+            Query(FeeRecommendQuery { urgency: Urgency::Medium }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(recommendation.surge_adjusted);
+        assert_eq!(recommendation.recommended_fee, 360);
+    }
+
+    #[tokio::test]
+    async fn recommend_fee_rejects_an_empty_history() {
+        let state = FeeRecommendState {
+            history: Arc::new(RwLock::new(FeeHistoryStore::new(10))),
+            insights: Arc::new(RwLock::new(FeeInsightsEngine::new(InsightsConfig::default()))),
+        };
+
+        let err = recommend_fee(
+            State(state),
+            Query(FeeRecommendQuery { urgency: Urgency::Low }),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, AppError::NotReady(_)));
+    }
 }
\ No newline at end of file
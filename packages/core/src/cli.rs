@@ -0,0 +1,19 @@
+use clap::Parser;
+
+/// Command-line flags. Any flag that is set overrides the corresponding
+/// environment variable in `Config::from_sources`.
+#[derive(Parser, Debug)]
+#[command(name = "stellar-fee-tracker", about = "Polls Horizon fee stats and serves them over HTTP")]
+pub struct Cli {
+    /// Comma-separated list of Horizon base URLs.
+    #[arg(long)]
+    pub horizon_url: Option<String>,
+
+    /// Port the HTTP API listens on.
+    #[arg(long)]
+    pub api_port: Option<u16>,
+
+    /// Seconds between fee polls.
+    #[arg(long)]
+    pub poll_interval_seconds: Option<u64>,
+}
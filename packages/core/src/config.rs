@@ -0,0 +1,259 @@
+use crate::cli::Cli;
+
+/// Backoff settings for `RetryClient`, shared by every Horizon request.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    /// Maximum number of retries after the initial attempt.
+    pub max_retries: u32,
+    /// Base delay for the first retry; doubles on each subsequent attempt.
+    pub initial_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff_ms: 250,
+        }
+    }
+}
+
+/// How `HorizonClient` reconciles readings when more than one Horizon
+/// endpoint is configured.
+#[derive(Debug, Clone, Copy)]
+pub enum ReconciliationMode {
+    /// Query endpoints in order, returning the first success.
+    Failover,
+    /// Query endpoints concurrently and accept the reading only if at
+    /// least `required` of them agree on `last_ledger_base_fee` within
+    /// `tolerance` stroops.
+    Quorum { required: usize, tolerance: u64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Comma-separated list of Horizon base URLs (one for a single host).
+    pub horizon_url: String,
+    pub api_port: u16,
+    pub poll_interval_seconds: u64,
+    pub allowed_origins: Vec<String>,
+    pub retry: RetryConfig,
+    pub reconciliation: ReconciliationMode,
+}
+
+impl Config {
+    /// Build configuration from environment variables, with CLI flags taking
+    /// precedence over their corresponding env var.
+    pub fn from_sources(cli: &Cli) -> Result<Config, String> {
+        let horizon_url = cli
+            .horizon_url
+            .clone()
+            .or_else(|| std::env::var("HORIZON_URL").ok())
+            .unwrap_or_else(|| "https://horizon.stellar.org".to_string());
+
+        // `HorizonClient::new` splits this on `,` and drops empty segments;
+        // catch a config that would leave it with zero usable endpoints here,
+        // with a clean error, rather than panicking on the first `base_url()` call.
+        if !horizon_url
+            .split(',')
+            .any(|url| !url.trim().trim_end_matches('/').is_empty())
+        {
+            return Err(format!(
+                "HORIZON_URL must contain at least one non-empty URL, got '{horizon_url}'"
+            ));
+        }
+
+        let api_port = match &cli.api_port {
+            Some(port) => *port,
+            None => match std::env::var("API_PORT") {
+                Ok(val) => val
+                    .parse()
+                    .map_err(|_| format!("API_PORT must be a valid port number, got '{val}'"))?,
+                Err(_) => 8080,
+            },
+        };
+
+        let poll_interval_seconds = match &cli.poll_interval_seconds {
+            Some(secs) => *secs,
+            None => match std::env::var("POLL_INTERVAL_SECONDS") {
+                Ok(val) => val.parse().map_err(|_| {
+                    format!("POLL_INTERVAL_SECONDS must be a positive integer, got '{val}'")
+                })?,
+                Err(_) => 10,
+            },
+        };
+
+        let allowed_origins = std::env::var("ALLOWED_ORIGINS")
+            .map(|val| val.split(',').map(|s| s.trim().to_string()).collect())
+            .unwrap_or_else(|_| vec!["http://localhost:3000".to_string()]);
+
+        let max_retries = std::env::var("HORIZON_MAX_RETRIES")
+            .ok()
+            .and_then(|val| val.parse().ok())
+            .unwrap_or(RetryConfig::default().max_retries);
+
+        let initial_backoff_ms = std::env::var("HORIZON_INITIAL_BACKOFF_MS")
+            .ok()
+            .and_then(|val| val.parse().ok())
+            .unwrap_or(RetryConfig::default().initial_backoff_ms);
+
+        let reconciliation = match std::env::var("HORIZON_RECONCILIATION_MODE").as_deref() {
+            Ok("quorum") => {
+                let required = std::env::var("HORIZON_QUORUM_SIZE")
+                    .ok()
+                    .and_then(|val| val.parse().ok())
+                    .unwrap_or(1);
+                let tolerance = std::env::var("HORIZON_QUORUM_TOLERANCE")
+                    .ok()
+                    .and_then(|val| val.parse().ok())
+                    .unwrap_or(0);
+
+                // A quorum size bigger than the endpoint list can never be
+                // reached, so every poll would permanently fail at runtime.
+                // Reject it here instead, same as the empty-`horizon_url` check above.
+                let endpoint_count = horizon_url
+                    .split(',')
+                    .filter(|url| !url.trim().trim_end_matches('/').is_empty())
+                    .count();
+                if required > endpoint_count {
+                    return Err(format!(
+                        "HORIZON_QUORUM_SIZE ({required}) cannot exceed the number of configured Horizon endpoints ({endpoint_count})"
+                    ));
+                }
+
+                ReconciliationMode::Quorum { required, tolerance }
+            }
+            _ => ReconciliationMode::Failover,
+        };
+
+        Ok(Config {
+            horizon_url,
+            api_port,
+            poll_interval_seconds,
+            allowed_origins,
+            retry: RetryConfig {
+                max_retries,
+                initial_backoff_ms,
+            },
+            reconciliation,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_cli() -> Cli {
+        Cli {
+            horizon_url: None,
+            api_port: None,
+            poll_interval_seconds: None,
+        }
+    }
+
+    fn clear_env() {
+        for var in [
+            "HORIZON_URL",
+            "API_PORT",
+            "POLL_INTERVAL_SECONDS",
+            "ALLOWED_ORIGINS",
+            "HORIZON_MAX_RETRIES",
+            "HORIZON_INITIAL_BACKOFF_MS",
+            "HORIZON_RECONCILIATION_MODE",
+            "HORIZON_QUORUM_SIZE",
+            "HORIZON_QUORUM_TOLERANCE",
+        ] {
+            std::env::remove_var(var);
+        }
+    }
+
+    // `std::env::var` is process-global and `cargo test` runs tests in
+    // parallel threads by default, so every env-driven scenario below runs
+    // sequentially inside this one test (clearing the vars it touches
+    // afterwards) instead of being split across separate `#[test]` fns,
+    // to avoid cross-test races over shared env state.
+    #[test]
+    fn from_sources_env_and_cli_scenarios() {
+        clear_env();
+
+        // Defaults apply when neither CLI flags nor env vars are set.
+        let config = Config::from_sources(&empty_cli()).unwrap();
+        assert_eq!(config.horizon_url, "https://horizon.stellar.org");
+        assert_eq!(config.api_port, 8080);
+        assert_eq!(config.poll_interval_seconds, 10);
+        assert_eq!(
+            config.allowed_origins,
+            vec!["http://localhost:3000".to_string()]
+        );
+        assert_eq!(config.retry.max_retries, RetryConfig::default().max_retries);
+        assert!(matches!(config.reconciliation, ReconciliationMode::Failover));
+
+        // CLI flags take precedence over the corresponding env vars.
+        std::env::set_var("API_PORT", "9999");
+        std::env::set_var("POLL_INTERVAL_SECONDS", "99");
+        let cli = Cli {
+            api_port: Some(1234),
+            poll_interval_seconds: Some(5),
+            ..empty_cli()
+        };
+        let config = Config::from_sources(&cli).unwrap();
+        assert_eq!(config.api_port, 1234);
+        assert_eq!(config.poll_interval_seconds, 5);
+        clear_env();
+
+        // An env var is used when no CLI flag overrides it.
+        std::env::set_var("API_PORT", "9999");
+        let config = Config::from_sources(&empty_cli()).unwrap();
+        assert_eq!(config.api_port, 9999);
+        clear_env();
+
+        // A malformed env value is a clean error, not a panic.
+        std::env::set_var("API_PORT", "not-a-port");
+        let err = Config::from_sources(&empty_cli()).unwrap_err();
+        assert!(err.contains("API_PORT"));
+        clear_env();
+
+        // `horizon_url` that splits down to zero usable endpoints is rejected.
+        let cli = Cli {
+            horizon_url: Some(" , ,  ,".to_string()),
+            ..empty_cli()
+        };
+        let err = Config::from_sources(&cli).unwrap_err();
+        assert!(err.contains("HORIZON_URL"));
+
+        // Failover is the default reconciliation mode.
+        let cli = Cli {
+            horizon_url: Some("https://a.example".to_string()),
+            ..empty_cli()
+        };
+        let config = Config::from_sources(&cli).unwrap();
+        assert!(matches!(config.reconciliation, ReconciliationMode::Failover));
+
+        // `HORIZON_RECONCILIATION_MODE=quorum` selects quorum mode, reading
+        // its size/tolerance from env.
+        std::env::set_var("HORIZON_RECONCILIATION_MODE", "quorum");
+        std::env::set_var("HORIZON_QUORUM_SIZE", "2");
+        std::env::set_var("HORIZON_QUORUM_TOLERANCE", "50");
+        let cli = Cli {
+            horizon_url: Some("https://a.example,https://b.example".to_string()),
+            ..empty_cli()
+        };
+        let config = Config::from_sources(&cli).unwrap();
+        assert!(matches!(
+            config.reconciliation,
+            ReconciliationMode::Quorum {
+                required: 2,
+                tolerance: 50
+            }
+        ));
+
+        // A quorum size bigger than the configured endpoint count is rejected
+        // rather than accepted into a permanently-unreachable quorum.
+        std::env::set_var("HORIZON_QUORUM_SIZE", "5");
+        let err = Config::from_sources(&cli).unwrap_err();
+        assert!(err.contains("HORIZON_QUORUM_SIZE"));
+
+        clear_env();
+    }
+}
@@ -0,0 +1,52 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::json;
+
+/// Top-level error type returned by config loading, Horizon calls, and API handlers.
+#[derive(Debug)]
+pub enum AppError {
+    /// Configuration failed to load (missing/invalid env var, bad CLI flag).
+    Config(String),
+    /// A request to Horizon failed (connection error or non-2xx status, after
+    /// any retries that apply), or the endpoints did not reach a quorum.
+    Network(String),
+    /// Horizon returned a body that could not be deserialised into the
+    /// expected shape.
+    Parse(String),
+    /// Horizon's response deserialised fine but failed a sanity check (e.g.
+    /// an unparseable fee, `min > max`, or non-monotonic percentiles).
+    Validation(String),
+    /// The request is well-formed but we don't have enough local state yet
+    /// to answer it (e.g. no fee history has been polled yet).
+    NotReady(String),
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::Config(msg) => write!(f, "configuration error: {msg}"),
+            AppError::Network(msg) => write!(f, "network error: {msg}"),
+            AppError::Parse(msg) => write!(f, "parse error: {msg}"),
+            AppError::Validation(msg) => write!(f, "validation error: {msg}"),
+            AppError::NotReady(msg) => write!(f, "not ready: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            AppError::Config(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::Network(_) => StatusCode::BAD_GATEWAY,
+            AppError::Parse(_) => StatusCode::BAD_GATEWAY,
+            AppError::Validation(_) => StatusCode::BAD_GATEWAY,
+            AppError::NotReady(_) => StatusCode::SERVICE_UNAVAILABLE,
+        };
+
+        let body = Json(json!({ "error": self.to_string() }));
+        (status, body).into_response()
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+
+use serde::Serialize;
+
+/// Tunables for the insights engine's surge detection.
+#[derive(Debug, Clone, Copy)]
+pub struct InsightsConfig {
+    /// Average ledger utilization ratio, over `surge_window` recent
+    /// readings, at or above which congestion is classified as likely.
+    pub surge_ratio_threshold: f64,
+    /// How many recent utilization readings the rolling average considers.
+    pub surge_window: usize,
+}
+
+impl Default for InsightsConfig {
+    fn default() -> Self {
+        Self {
+            surge_ratio_threshold: 0.85,
+            surge_window: 12,
+        }
+    }
+}
+
+/// Whether recent ledger utilization suggests a fee surge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SurgeClassification {
+    Normal,
+    SurgeLikely,
+}
+
+/// Tracks recent ledger-utilization ratios and classifies congestion.
+///
+/// Fed by the poller (one reading per poll cycle) and read by the fees API
+/// to surface a "surge likely / normal" signal alongside raw fee stats.
+pub struct FeeInsightsEngine {
+    config: InsightsConfig,
+    utilization_history: VecDeque<f64>,
+}
+
+impl FeeInsightsEngine {
+    pub fn new(config: InsightsConfig) -> Self {
+        Self {
+            utilization_history: VecDeque::with_capacity(config.surge_window),
+            config,
+        }
+    }
+
+    /// Record a newly observed ledger-utilization ratio (tx count /
+    /// `max_tx_set_size`), evicting the oldest reading once the window is full.
+    pub fn record_utilization(&mut self, ratio: f64) {
+        if self.utilization_history.len() >= self.config.surge_window {
+            self.utilization_history.pop_front();
+        }
+        self.utilization_history.push_back(ratio);
+    }
+
+    /// The most recently recorded utilization ratio, if any.
+    pub fn latest_utilization(&self) -> Option<f64> {
+        self.utilization_history.back().copied()
+    }
+
+    /// Classify congestion from the rolling average utilization ratio.
+    pub fn classification(&self) -> SurgeClassification {
+        if self.utilization_history.is_empty() {
+            return SurgeClassification::Normal;
+        }
+
+        let avg =
+            self.utilization_history.iter().sum::<f64>() / self.utilization_history.len() as f64;
+
+        if avg >= self.config.surge_ratio_threshold {
+            SurgeClassification::SurgeLikely
+        } else {
+            SurgeClassification::Normal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine(threshold: f64, window: usize) -> FeeInsightsEngine {
+        FeeInsightsEngine::new(InsightsConfig {
+            surge_ratio_threshold: threshold,
+            surge_window: window,
+        })
+    }
+
+    #[test]
+    fn classification_is_normal_with_no_readings() {
+        let engine = engine(0.8, 5);
+        assert_eq!(engine.classification(), SurgeClassification::Normal);
+    }
+
+    #[test]
+    fn classification_flags_surge_once_average_crosses_threshold() {
+        let mut engine = engine(0.8, 5);
+        engine.record_utilization(0.9);
+        engine.record_utilization(0.95);
+        assert_eq!(engine.classification(), SurgeClassification::SurgeLikely);
+    }
+
+    #[test]
+    fn window_evicts_oldest_reading_once_full() {
+        let mut engine = engine(0.8, 2);
+        engine.record_utilization(0.95);
+        engine.record_utilization(0.1);
+        engine.record_utilization(0.1);
+
+        // Only the last two readings (0.1, 0.1) should count now.
+        assert_eq!(engine.classification(), SurgeClassification::Normal);
+        assert_eq!(engine.latest_utilization(), Some(0.1));
+    }
+}
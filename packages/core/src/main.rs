@@ -25,7 +25,7 @@ use tower_http::cors::{AllowOrigin, CorsLayer};
 use crate::cli::Cli;
 use crate::config::Config;
 use crate::error::AppError;
-use crate::insights::{FeeInsightsEngine, InsightsConfig, HorizonFeeDataProvider};
+use crate::insights::{FeeInsightsEngine, InsightsConfig};
 use crate::logging::init_logging;
 use crate::scheduler::run_fee_polling;
 use crate::services::horizon::HorizonClient;
@@ -53,18 +53,35 @@ async fn main() {
     tracing::info!("Configuration loaded: {:?}", config);
 
     // ---- Shared state ----
-    let horizon_client = Arc::new(HorizonClient::new(config.horizon_url.clone()));
-    tracing::info!("Horizon client initialized: {}", horizon_client.base_url());
+    let horizon_client = Arc::new(HorizonClient::new(
+        config.horizon_url.clone(),
+        config.retry,
+        config.reconciliation,
+    ));
+    tracing::info!(
+        "Horizon client initialized with {} endpoint(s), primary: {}",
+        horizon_client.base_urls().len(),
+        horizon_client.base_url()
+    );
 
     let fee_store = Arc::new(RwLock::new(FeeHistoryStore::new(DEFAULT_CAPACITY)));
 
+    // Broadcast channel the poller publishes each snapshot on; every
+    // `/fees/stream` subscriber gets its own receiver via `.subscribe()`.
+    let (fee_updates_tx, _) = tokio::sync::broadcast::channel(DEFAULT_CAPACITY);
+
     let insights_engine = Arc::new(RwLock::new(
         FeeInsightsEngine::new(InsightsConfig::default()),
     ));
 
-    let horizon_provider = Arc::new(HorizonFeeDataProvider::new(
-        (*horizon_client).clone(),
-    ));
+    // Shared shutdown flag: flips to `true` on SIGTERM, SIGHUP, or SIGINT, and
+    // is watched by both the axum server (graceful shutdown) and the poller.
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    tokio::spawn(async move {
+        wait_for_shutdown_signal().await;
+        tracing::info!("Shutdown signal received, draining in-flight work");
+        let _ = shutdown_tx.send(true);
+    });
 
     // ---- CORS policy ----
     let origins: Vec<axum::http::HeaderValue> = config
@@ -103,11 +120,35 @@ async fn main() {
     // Both sub-routers are Router<()> after with_state, so merge works fine
     let fees_router = Router::new()
         .route("/fees/current", get(api::fees::current_fees))
-        .with_state(horizon_client.clone());
+        .with_state(api::fees::FeesState {
+            horizon: horizon_client.clone(),
+            insights: insights_engine.clone(),
+        });
+
+    let fee_history_router = Router::new()
+        .route("/fees/history", get(api::fees::fee_history))
+        .with_state(fee_store.clone());
+
+    let fee_stream_router = Router::new()
+        .route("/fees/stream", get(api::fees::fee_stream))
+        .with_state(api::fees::FeeStreamState {
+            updates: fee_updates_tx.clone(),
+            shutdown: shutdown_rx.clone(),
+        });
+
+    let fee_recommend_router = Router::new()
+        .route("/fees/recommend", get(api::fees::recommend_fee))
+        .with_state(api::fees::FeeRecommendState {
+            history: fee_store.clone(),
+            insights: insights_engine.clone(),
+        });
 
     let app = Router::new()
         .route("/health", get(api::health::health))
         .merge(fees_router)
+        .merge(fee_history_router)
+        .merge(fee_stream_router)
+        .merge(fee_recommend_router)
         .merge(api::insights::create_insights_router(insights_engine.clone()))
         .layer(cors);
 
@@ -123,19 +164,41 @@ async fn main() {
     tracing::info!("API server listening on {}", addr);
 
     // ---- Run server + scheduler concurrently ----
+    let mut server_shutdown_rx = shutdown_rx.clone();
     tokio::join!(
         async {
             axum::serve(listener, app)
+                .with_graceful_shutdown(async move {
+                    let _ = server_shutdown_rx.changed().await;
+                })
                 .await
                 .unwrap_or_else(|err| tracing::error!("Server error: {}", err));
         },
         run_fee_polling(
-            horizon_provider,
-            fee_store,
-            insights_engine,
+            (*horizon_client).clone(),
+            fee_store.clone(),
+            fee_updates_tx,
+            insights_engine.clone(),
+            shutdown_rx,
             config.poll_interval_seconds,
         ),
     );
 
     tracing::info!("Application shut down cleanly");
+}
+
+/// Resolves on the first of SIGTERM, SIGHUP, or SIGINT (Ctrl-C) — the
+/// signals under which systemd and interactive shells ask us to stop.
+async fn wait_for_shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sigterm =
+        signal(SignalKind::terminate()).expect("failed to install SIGTERM handler");
+    let mut sighup = signal(SignalKind::hangup()).expect("failed to install SIGHUP handler");
+
+    tokio::select! {
+        _ = sigterm.recv() => tracing::info!("Received SIGTERM"),
+        _ = sighup.recv() => tracing::info!("Received SIGHUP"),
+        _ = tokio::signal::ctrl_c() => tracing::info!("Received SIGINT (Ctrl-C)"),
+    }
 }
\ No newline at end of file
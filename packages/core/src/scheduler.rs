@@ -1,9 +1,12 @@
+use std::sync::Arc;
 use std::time::Duration;
 
-use tokio::signal;
+use tokio::sync::{broadcast, watch, RwLock};
 use tokio::time;
 
-use crate::services::horizon::HorizonClient;
+use crate::insights::FeeInsightsEngine;
+use crate::services::horizon::{self, HorizonClient};
+use crate::store::{FeeHistoryStore, FeeSnapshot};
 
 
 
@@ -13,6 +16,10 @@ use crate::services::horizon::HorizonClient;
 
 pub async fn run_fee_polling(
     horizon_client: HorizonClient,
+    fee_store: Arc<RwLock<FeeHistoryStore>>,
+    fee_updates: broadcast::Sender<FeeSnapshot>,
+    insights_engine: Arc<RwLock<FeeInsightsEngine>>,
+    mut shutdown: watch::Receiver<bool>,
     poll_interval_seconds: u64,
 ) {
     let mut interval = time::interval(Duration::from_secs(poll_interval_seconds));
@@ -34,16 +41,37 @@ pub async fn run_fee_polling(
                             stats.fee_charged.max,
                             stats.fee_charged.avg
                         );
+
+                        let snapshot = FeeSnapshot::from_stats(&stats);
+                        fee_store.write().await.push(snapshot.clone());
+
+                        // No receivers (e.g. no SSE clients connected) is not an error.
+                        let _ = fee_updates.send(snapshot);
                     }
                     Err(err) => {
                         tracing::error!("Fee polling error: {}", err);
                     }
                 }
+
+                match horizon_client.fetch_latest_ledger().await {
+                    Ok(ledger) => match horizon::utilization_ratio(&ledger) {
+                        Ok(ratio) => {
+                            tracing::info!("Ledger utilization: {:.2}%", ratio * 100.0);
+                            insights_engine.write().await.record_utilization(ratio);
+                        }
+                        Err(err) => tracing::warn!("Rejected ledger reading: {}", err),
+                    },
+                    Err(err) => tracing::error!("Ledger polling error: {}", err),
+                }
             }
 
-            _ = signal::ctrl_c() => {
-                tracing::info!("Shutdown signal received. Stopping polling.");
-                break;
+            // `changed()` only resolves once the shutdown flag flips, so an
+            // in-flight poll above always runs to completion first.
+            _ = shutdown.changed() => {
+                if *shutdown.borrow() {
+                    tracing::info!("Shutdown signal received. Stopping polling.");
+                    break;
+                }
             }
         }
     }
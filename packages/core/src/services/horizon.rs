@@ -1,26 +1,190 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use serde::Deserialize;
-use reqwest::Client;
+use reqwest::{Client, Response, StatusCode};
 
+use crate::config::{ReconciliationMode, RetryConfig};
 use crate::error::AppError;
 
 
 #[derive(Clone)]
 pub struct HorizonClient {
-    base_url: String,
+    /// One or more Horizon base URLs. The first is treated as primary for
+    /// requests that don't reconcile across endpoints.
+    base_urls: Vec<String>,
     http: Client,
+    retry: RetryConfig,
+    reconciliation: ReconciliationMode,
 }
 
 impl HorizonClient {
-    pub fn new(base_url: String) -> Self {
+    /// `base_url` may be a single URL or a comma-separated list of
+    /// redundant Horizon hosts (e.g. public mirrors).
+    pub fn new(base_url: String, retry: RetryConfig, reconciliation: ReconciliationMode) -> Self {
+        let base_urls = base_url
+            .split(',')
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty())
+            .collect();
+
         Self {
-            base_url,
+            base_urls,
             http: Client::new(),
+            retry,
+            reconciliation,
         }
     }
 
+    /// The primary (first configured) Horizon base URL, used for requests
+    /// that are not reconciled across endpoints.
     pub fn base_url(&self) -> &str {
-        &self.base_url
+        &self.base_urls[0]
+    }
+
+    pub fn base_urls(&self) -> &[String] {
+        &self.base_urls
+    }
+
+    /// Send a GET request, retrying on connection errors, 5xx, and 429
+    /// responses with exponential backoff + jitter, honoring `Retry-After`
+    /// when Horizon sends one. Non-retryable 4xx responses fail immediately.
+    async fn get_with_retry(&self, url: &str) -> Result<Response, AppError> {
+        let mut attempt = 0u32;
+
+        loop {
+            match self.http.get(url).send().await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => {
+                    let status = response.status();
+                    if !is_retryable_status(status) || attempt >= self.retry.max_retries {
+                        return Err(AppError::Network(format!(
+                            "Horizon returned HTTP {status}"
+                        )));
+                    }
+
+                    let delay =
+                        retry_after_delay(&response).unwrap_or_else(|| self.backoff_delay(attempt));
+                    tracing::warn!(
+                        "Horizon request to {} failed with {} (attempt {}/{}), retrying in {:?}",
+                        url,
+                        status,
+                        attempt + 1,
+                        self.retry.max_retries,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(err) => {
+                    if attempt >= self.retry.max_retries {
+                        return Err(AppError::Network(err.to_string()));
+                    }
+
+                    let delay = self.backoff_delay(attempt);
+                    tracing::warn!(
+                        "Horizon request to {} errored: {} (attempt {}/{}), retrying in {:?}",
+                        url,
+                        err,
+                        attempt + 1,
+                        self.retry.max_retries,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        let base_ms = self
+            .retry
+            .initial_backoff_ms
+            .saturating_mul(2u64.saturating_pow(attempt));
+        Duration::from_millis(base_ms.saturating_add(jitter_ms(base_ms)))
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Parse a `Retry-After` header (delay-seconds or an HTTP-date) into a
+/// concrete sleep duration, per RFC 7231 §7.1.3.
+fn retry_after_delay(response: &Response) -> Option<Duration> {
+    let value = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?;
+
+    if let Ok(secs) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let target_unix = parse_http_date(value.trim())?;
+    let now_unix = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some(Duration::from_secs(target_unix.saturating_sub(now_unix)))
+}
+
+/// Minimal parser for the RFC 7231 IMF-fixdate form of an HTTP-date, e.g.
+/// `Sat, 06 Jan 2024 12:00:00 GMT`. Returns seconds since the Unix epoch.
+fn parse_http_date(value: &str) -> Option<u64> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    if parts.len() != 6 {
+        return None;
     }
+
+    let day: u64 = parts[1].parse().ok()?;
+    let month = match parts[2] {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+    let year: u64 = parts[3].parse().ok()?;
+
+    let mut time_parts = parts[4].split(':');
+    let hour: u64 = time_parts.next()?.parse().ok()?;
+    let min: u64 = time_parts.next()?.parse().ok()?;
+    let sec: u64 = time_parts.next()?.parse().ok()?;
+
+    let days_since_epoch = days_from_civil(year, month, day);
+    Some(days_since_epoch * 86_400 + hour * 3_600 + min * 60 + sec)
+}
+
+/// Howard Hinnant's days-from-civil algorithm for the Gregorian calendar.
+/// Returns days since 1970-01-01; the arithmetic is unsigned, so dates
+/// before 1970-01-01 underflow.
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn jitter_ms(base_ms: u64) -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    let spread = (base_ms / 4).max(1);
+    nanos % spread
 }
 
 
@@ -46,13 +210,13 @@ pub struct HorizonOperation {
     pub amount: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct HorizonFeeStats {
     pub last_ledger_base_fee: String,
     pub fee_charged: FeeCharged,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct FeeCharged {
     pub min: String,
     pub max: String,
@@ -65,30 +229,246 @@ pub struct FeeCharged {
     pub p95: String,
 }
 
+impl HorizonFeeStats {
+    /// Sanity-check a deserialised reading before it enters the history
+    /// store or feeds the insights engine: the base fee must be a parseable
+    /// integer, `min` must not exceed `max`, and percentiles must be
+    /// monotonic non-decreasing (p10 <= p25 <= ... <= p95).
+    pub fn validate(&self) -> Result<(), AppError> {
+        self.last_ledger_base_fee
+            .parse::<u64>()
+            .map_err(|_| AppError::Validation(format!(
+                "last_ledger_base_fee is not a valid integer: '{}'",
+                self.last_ledger_base_fee
+            )))?;
+
+        let fc = &self.fee_charged;
+        let min: u64 = fc
+            .min
+            .parse()
+            .map_err(|_| AppError::Validation(format!("fee_charged.min is not a valid integer: '{}'", fc.min)))?;
+        let max: u64 = fc
+            .max
+            .parse()
+            .map_err(|_| AppError::Validation(format!("fee_charged.max is not a valid integer: '{}'", fc.max)))?;
+
+        if min > max {
+            return Err(AppError::Validation(format!(
+                "fee_charged.min ({min}) is greater than fee_charged.max ({max})"
+            )));
+        }
+
+        let percentiles = [
+            ("p10", &fc.p10),
+            ("p25", &fc.p25),
+            ("p50", &fc.p50),
+            ("p75", &fc.p75),
+            ("p90", &fc.p90),
+            ("p95", &fc.p95),
+        ];
+
+        let mut previous = 0u64;
+        for (name, raw) in percentiles {
+            let value: u64 = raw
+                .parse()
+                .map_err(|_| AppError::Validation(format!("fee_charged.{name} is not a valid integer: '{raw}'")))?;
+            if value < previous {
+                return Err(AppError::Validation(format!(
+                    "fee_charged percentiles are not monotonic non-decreasing: {name} ({value}) < previous ({previous})"
+                )));
+            }
+            previous = value;
+        }
+
+        Ok(())
+    }
+}
+
+/// A Horizon ledger record, trimmed to the fields needed to compute
+/// ledger utilization (how full the ledger's transaction set was).
+#[derive(Debug, Clone, Deserialize)]
+pub struct HorizonLedger {
+    pub transaction_count: u64,
+    pub max_tx_set_size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct HorizonLedgerResponse {
+    #[serde(rename = "_embedded")]
+    embedded: HorizonLedgerEmbedded,
+}
+
+#[derive(Debug, Deserialize)]
+struct HorizonLedgerEmbedded {
+    records: Vec<HorizonLedger>,
+}
+
+/// Ledger utilization ratio (transactions included / capacity), the
+/// Stellar analogue of Ethereum's gas-used ratio. Always in `[0, 1]`.
+pub fn utilization_ratio(ledger: &HorizonLedger) -> Result<f64, AppError> {
+    if ledger.max_tx_set_size == 0 {
+        return Err(AppError::Validation(
+            "ledger max_tx_set_size is zero, cannot compute utilization".into(),
+        ));
+    }
+
+    let ratio = ledger.transaction_count as f64 / ledger.max_tx_set_size as f64;
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(AppError::Validation(format!(
+            "ledger utilization ratio {ratio} is outside the valid [0, 1] range"
+        )));
+    }
+
+    Ok(ratio)
+}
 
 impl HorizonClient {
-    pub async fn fetch_fee_stats(&self) -> Result<HorizonFeeStats, AppError> {
-        let url = format!("{}/fee_stats", self.base_url);
+    /// Fetch the most recent ledger, used to derive the utilization ratio.
+    pub async fn fetch_latest_ledger(&self) -> Result<HorizonLedger, AppError> {
+        let url = format!("{}/ledgers?order=desc&limit=1", self.base_url());
+
+        let response = self.get_with_retry(&url).await?;
 
-        let response = self
-            .http
-            .get(&url)
-            .send()
+        let body = response
+            .json::<HorizonLedgerResponse>()
             .await
-            .map_err(|err| AppError::Network(err.to_string()))?;
+            .map_err(|e| AppError::Parse(e.to_string()))?;
 
-        if !response.status().is_success() {
-            return Err(AppError::Network(format!(
-                "Horizon returned HTTP {}",
-                response.status()
-            )));
+        body.embedded
+            .records
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::Parse("Horizon returned empty ledger records".into()))
+    }
+}
+
+
+impl HorizonClient {
+    /// Fetch current fee stats, reconciled across all configured Horizon
+    /// endpoints per `self.reconciliation`.
+    pub async fn fetch_fee_stats(&self) -> Result<HorizonFeeStats, AppError> {
+        match self.reconciliation {
+            ReconciliationMode::Failover => self.fetch_fee_stats_failover().await,
+            ReconciliationMode::Quorum { required, tolerance } => {
+                self.fetch_fee_stats_quorum(required, tolerance).await
+            }
         }
+    }
+
+    /// Query endpoints in order, returning the first successful reading.
+    async fn fetch_fee_stats_failover(&self) -> Result<HorizonFeeStats, AppError> {
+        let mut last_err = None;
+
+        for base_url in &self.base_urls {
+            match self.fetch_fee_stats_from(base_url).await {
+                Ok(stats) => return Ok(stats),
+                Err(err) => {
+                    tracing::warn!("Horizon endpoint {} failed: {}", base_url, err);
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AppError::Network("no Horizon endpoints configured".into())))
+    }
+
+    /// Query every configured endpoint concurrently and only accept the
+    /// reading if at least `required` of them agree on
+    /// `last_ledger_base_fee`: each reading joins the first group whose
+    /// anchor (its first reading) is within `tolerance` stroops of it.
+    async fn fetch_fee_stats_quorum(
+        &self,
+        required: usize,
+        tolerance: u64,
+    ) -> Result<HorizonFeeStats, AppError> {
+        let handles: Vec<_> = self
+            .base_urls
+            .iter()
+            .map(|base_url| {
+                let client = self.clone();
+                let base_url = base_url.clone();
+                tokio::spawn(async move { client.fetch_fee_stats_from(&base_url).await })
+            })
+            .collect();
+
+        let mut readings = Vec::with_capacity(handles.len());
+        for handle in handles {
+            match handle.await {
+                Ok(Ok(stats)) => readings.push(stats),
+                Ok(Err(err)) => tracing::warn!("Horizon endpoint failed during quorum read: {}", err),
+                Err(err) => tracing::warn!("Horizon quorum task panicked: {}", err),
+            }
+        }
+
+        if readings.is_empty() {
+            return Err(AppError::Network(
+                "all Horizon endpoints failed during quorum read".into(),
+            ));
+        }
+
+        // Group readings whose base fee agrees within `tolerance` and take
+        // the largest agreeing group.
+        let mut groups: Vec<(u64, Vec<HorizonFeeStats>)> = Vec::new();
+        for stats in readings {
+            let Some(base_fee) = stats.last_ledger_base_fee.parse::<u64>().ok() else {
+                continue;
+            };
+
+            if let Some(group) = groups
+                .iter_mut()
+                .find(|(anchor, _)| base_fee.abs_diff(*anchor) <= tolerance)
+            {
+                group.1.push(stats);
+            } else {
+                groups.push((base_fee, vec![stats]));
+            }
+        }
+
+        let best_group = groups
+            .into_iter()
+            .max_by_key(|(_, members)| members.len());
+
+        match best_group {
+            Some((anchor, members)) if members.len() >= required => {
+                tracing::info!(
+                    "Quorum reached: {}/{} endpoints agree on base fee {}",
+                    members.len(),
+                    required,
+                    anchor
+                );
+                Ok(members.into_iter().next().unwrap())
+            }
+            Some((anchor, members)) => {
+                tracing::warn!(
+                    "Horizon endpoints disagree on base fee: only {}/{} required endpoints agree (anchor {})",
+                    members.len(),
+                    required,
+                    anchor
+                );
+                Err(AppError::Network(format!(
+                    "quorum not reached: {}/{} endpoints agreed",
+                    members.len(),
+                    required
+                )))
+            }
+            None => Err(AppError::Parse(
+                "no Horizon endpoint returned a parseable base fee".into(),
+            )),
+        }
+    }
+
+    async fn fetch_fee_stats_from(&self, base_url: &str) -> Result<HorizonFeeStats, AppError> {
+        let url = format!("{base_url}/fee_stats");
+
+        let response = self.get_with_retry(&url).await?;
 
         let stats = response
             .json::<HorizonFeeStats>()
             .await
             .map_err(|err| AppError::Parse(err.to_string()))?;
 
+        stats.validate()?;
+
         Ok(stats)
     }
 }
@@ -124,21 +504,9 @@ impl HorizonClient {
     /// the first record. Returns `AppError::Parse` if Horizon returns an
     /// empty records array.
     pub async fn fetch_latest_transaction(&self) -> Result<HorizonTransaction, AppError> {
-        let url = format!("{}/transactions?order=desc&limit=1", self.base_url);
+        let url = format!("{}/transactions?order=desc&limit=1", self.base_url());
 
-        let response = self
-            .http
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| AppError::Network(e.to_string()))?;
-
-        if !response.status().is_success() {
-            return Err(AppError::Network(format!(
-                "Horizon returned HTTP {}",
-                response.status()
-            )));
-        }
+        let response = self.get_with_retry(&url).await?;
 
         let body = response
             .json::<HorizonTransactionResponse>()
@@ -157,21 +525,9 @@ impl HorizonClient {
     /// Calls `GET {base_url}/transactions/{tx_hash}/operations` and returns
     /// the full records vec (may be empty for transactions with no operations).
     pub async fn fetch_operations(&self, tx_hash: &str) -> Result<Vec<HorizonOperation>, AppError> {
-        let url = format!("{}/transactions/{}/operations", self.base_url, tx_hash);
+        let url = format!("{}/transactions/{}/operations", self.base_url(), tx_hash);
 
-        let response = self
-            .http
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| AppError::Network(e.to_string()))?;
-
-        if !response.status().is_success() {
-            return Err(AppError::Network(format!(
-                "Horizon returned HTTP {}",
-                response.status()
-            )));
-        }
+        let response = self.get_with_retry(&url).await?;
 
         let body = response
             .json::<HorizonOperationsResponse>()
@@ -191,10 +547,64 @@ mod tests {
 
     #[test]
     fn horizon_client_base_url_is_stored() {
-        let client = HorizonClient::new("https://horizon-testnet.stellar.org".into());
+        let client = HorizonClient::new(
+            "https://horizon-testnet.stellar.org".into(),
+            RetryConfig::default(),
+            ReconciliationMode::Failover,
+        );
         assert_eq!(client.base_url(), "https://horizon-testnet.stellar.org");
     }
 
+    #[test]
+    fn comma_separated_base_urls_are_split_and_trimmed() {
+        let client = HorizonClient::new(
+            " https://horizon1.example.org/ , https://horizon2.example.org".into(),
+            RetryConfig::default(),
+            ReconciliationMode::Failover,
+        );
+        assert_eq!(
+            client.base_urls(),
+            &[
+                "https://horizon1.example.org".to_string(),
+                "https://horizon2.example.org".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn retryable_statuses_include_5xx_and_429() {
+        assert!(is_retryable_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+    }
+
+    #[test]
+    fn non_retryable_4xx_statuses_are_not_retried() {
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+    }
+
+    #[test]
+    fn parse_http_date_matches_known_epoch_seconds() {
+        // 2024-01-06T12:00:00Z
+        let secs = parse_http_date("Sat, 06 Jan 2024 12:00:00 GMT").unwrap();
+        assert_eq!(secs, 1_704_542_400);
+    }
+
+    #[test]
+    fn parse_http_date_rejects_malformed_input() {
+        assert!(parse_http_date("not a date").is_none());
+    }
+
+    #[test]
+    fn jitter_is_bounded_by_a_quarter_of_base() {
+        for _ in 0..20 {
+            let jitter = jitter_ms(1000);
+            assert!(jitter < 250);
+        }
+    }
+
     #[test]
     fn horizon_transaction_deserialises_from_json() {
         let json = r#"{"hash":"abc123","successful":true,"fee_charged":"100"}"#;
@@ -298,4 +708,55 @@ mod tests {
         assert_eq!(stats.fee_charged.p50, "150");
         assert_eq!(stats.fee_charged.p95, "800");
     }
+
+    fn valid_fee_stats() -> HorizonFeeStats {
+        serde_json::from_str(
+            r#"{"last_ledger_base_fee":"100","fee_charged":{"min":"100","max":"5000","avg":"213","p10":"100","p25":"100","p50":"150","p75":"300","p90":"500","p95":"800"}}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn validate_accepts_a_well_formed_reading() {
+        assert!(valid_fee_stats().validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_unparseable_base_fee() {
+        let mut stats = valid_fee_stats();
+        stats.last_ledger_base_fee = "not-a-number".into();
+        assert!(matches!(stats.validate(), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn validate_rejects_min_greater_than_max() {
+        let mut stats = valid_fee_stats();
+        stats.fee_charged.min = "9000".into();
+        assert!(matches!(stats.validate(), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn validate_rejects_non_monotonic_percentiles() {
+        let mut stats = valid_fee_stats();
+        stats.fee_charged.p75 = "50".into();
+        assert!(matches!(stats.validate(), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn utilization_ratio_computes_fraction_of_capacity() {
+        let ledger = HorizonLedger {
+            transaction_count: 50,
+            max_tx_set_size: 100,
+        };
+        assert_eq!(utilization_ratio(&ledger).unwrap(), 0.5);
+    }
+
+    #[test]
+    fn utilization_ratio_rejects_zero_capacity() {
+        let ledger = HorizonLedger {
+            transaction_count: 1,
+            max_tx_set_size: 0,
+        };
+        assert!(matches!(utilization_ratio(&ledger), Err(AppError::Validation(_))));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,129 @@
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::services::horizon::HorizonFeeStats;
+
+/// Default number of polled fee snapshots retained in memory.
+///
+/// At the default 10s poll interval this covers roughly 40 minutes of
+/// history, which is enough for the `/fees/history` chart and the
+/// `/fees/recommend` sample window without unbounded growth.
+pub const DEFAULT_CAPACITY: usize = 256;
+
+/// A single polled fee observation, timestamped when it was fetched.
+#[derive(Debug, Clone)]
+pub struct FeeSnapshot {
+    pub base_fee: String,
+    pub min_fee: String,
+    pub max_fee: String,
+    pub avg_fee: String,
+    pub p10: String,
+    pub p25: String,
+    pub p50: String,
+    pub p75: String,
+    pub p90: String,
+    pub p95: String,
+    /// Unix timestamp (seconds) at which this snapshot was polled.
+    pub polled_at: u64,
+}
+
+impl FeeSnapshot {
+    /// Build a snapshot from a raw Horizon response, stamped with the
+    /// current wall-clock time.
+    pub fn from_stats(stats: &HorizonFeeStats) -> Self {
+        Self {
+            base_fee: stats.last_ledger_base_fee.clone(),
+            min_fee: stats.fee_charged.min.clone(),
+            max_fee: stats.fee_charged.max.clone(),
+            avg_fee: stats.fee_charged.avg.clone(),
+            p10: stats.fee_charged.p10.clone(),
+            p25: stats.fee_charged.p25.clone(),
+            p50: stats.fee_charged.p50.clone(),
+            p75: stats.fee_charged.p75.clone(),
+            p90: stats.fee_charged.p90.clone(),
+            p95: stats.fee_charged.p95.clone(),
+            polled_at: now_unix(),
+        }
+    }
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Fixed-capacity ring buffer of recently polled fee snapshots.
+///
+/// Oldest entries are evicted once `capacity` is reached so memory use
+/// stays bounded regardless of how long the poller runs.
+pub struct FeeHistoryStore {
+    capacity: usize,
+    entries: VecDeque<FeeSnapshot>,
+}
+
+impl FeeHistoryStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Push a new snapshot, evicting the oldest entry if at capacity.
+    pub fn push(&mut self, snapshot: FeeSnapshot) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(snapshot);
+    }
+
+    /// Return up to the last `n` snapshots, oldest first.
+    pub fn last_n(&self, n: usize) -> Vec<FeeSnapshot> {
+        let skip = self.entries.len().saturating_sub(n);
+        self.entries.iter().skip(skip).cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(base_fee: &str) -> HorizonFeeStats {
+        serde_json::from_str(&format!(
+            r#"{{"last_ledger_base_fee":"{base_fee}","fee_charged":{{"min":"100","max":"5000","avg":"213","p10":"100","p25":"100","p50":"150","p75":"300","p90":"500","p95":"800"}}}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn push_evicts_oldest_once_at_capacity() {
+        let mut store = FeeHistoryStore::new(2);
+        store.push(FeeSnapshot::from_stats(&stats("100")));
+        store.push(FeeSnapshot::from_stats(&stats("200")));
+        store.push(FeeSnapshot::from_stats(&stats("300")));
+
+        let entries = store.last_n(10);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].base_fee, "200");
+        assert_eq!(entries[1].base_fee, "300");
+    }
+
+    #[test]
+    fn last_n_caps_at_available_entries() {
+        let mut store = FeeHistoryStore::new(10);
+        store.push(FeeSnapshot::from_stats(&stats("100")));
+
+        let entries = store.last_n(5);
+        assert_eq!(entries.len(), 1);
+    }
+}